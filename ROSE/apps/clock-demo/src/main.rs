@@ -5,7 +5,7 @@ extern crate sdl2;
 extern crate sdlstate;
 extern crate stencil;
 
-use stencil::stencil::Stencil;
+use stencil::stencil::{coalesce_rects, Stencil};
 use stencil::types::{Dimension, Point, Rect, Unit};
 
 use sdl2::event::{Event, WindowEvent};
@@ -77,7 +77,7 @@ fn main() {
             let command = match event {
                 Event::Quit { .. } => HostAction::Quit,
                 Event::Window { win_event: we, .. } if we == WindowEvent::Exposed => {
-                    HostAction::Repaint(((0, 0), (W, H)))
+                    HostAction::Repaint(vec![((0, 0), (W, H))])
                 }
                 Event::MouseButtonUp {
                     mouse_btn: b, x, y, ..
@@ -104,7 +104,15 @@ fn main() {
             };
 
             match command {
-                HostAction::Repaint(_) => repaint(&mut desktop, &mut sdl),
+                HostAction::Repaint(full) => {
+                    // Prefer the precise damage the stencil itself recorded over the command's
+                    // own rectangle, coalescing adjacent regions before handing them to the host.
+                    // `full` remains the fallback for repaints with no stencil damage to report,
+                    // such as a window-expose event.
+                    let damage = coalesce_rects(desktop.take_damage());
+                    let damage = if damage.is_empty() { full } else { damage };
+                    repaint(&mut desktop, &mut sdl, damage);
+                }
                 HostAction::Quit => break 'main_event_loop,
                 _ => (),
             }
@@ -125,7 +133,7 @@ fn button_for(b: MouseButton) -> usize {
 pub enum HostAction {
     None,
     Quit,
-    Repaint(Rect),
+    Repaint(Vec<Rect>),
 }
 
 pub enum Cmd {
@@ -135,28 +143,30 @@ pub enum Cmd {
     TimerTick,
 }
 
-/// Repaint the screen and make it visible to the human operator.
+/// Repaint `damage` and make it visible to the human operator.
 ///
 /// This function performs color-expansion and/or retiling as appropriate to render the contents of
-/// the `desktop` stencil to the display.
-fn repaint(desktop: &mut Stencil, sdl: &mut SdlState) {
-    // Sadly, because of how SDL2 works with modern video equipment,
-    // we must refresh the entire surface; we can't be clever and just
-    // refresh a subset of a surface.  Therefore, the `r` parameter is
-    // unused.
-    let ((left, top), (right, bottom)) = ((0, 0), desktop.dimensions);
-    let (left, top) = (left as usize, top as usize);
-    let (right, bottom) = (right as usize, bottom as usize);
-    let width = right - left;
-    let height = bottom - top;
-
+/// the `desktop` stencil to the display. `damage` is the set of rectangles that actually changed —
+/// the rectangles reported by [[Stencil::take_damage]], coalesced through
+/// `stencil::stencil::coalesce_rects` by the caller — so a tick that only moves the clock's second
+/// hand re-uploads a handful of small regions rather than the entire 320x200 surface. When the
+/// stencil recorded no damage, the caller passes the command's own rectangles instead (the whole
+/// surface, for a window-expose event).
+fn repaint(desktop: &mut Stencil, sdl: &mut SdlState, damage: Vec<Rect>) {
     sdl.paint_with(|ctx| {
-        ctx.paste_stamp_be(
-            (left, top),
-            (width, height),
-            desktop.get_span(),
-            (left, top),
-            desktop.borrow_bits(),
-        );
+        for ((left, top), (right, bottom)) in damage {
+            let (left, top) = (left as usize, top as usize);
+            let (right, bottom) = (right as usize, bottom as usize);
+            let width = right - left;
+            let height = bottom - top;
+
+            ctx.paste_stamp_be(
+                (left, top),
+                (width, height),
+                desktop.get_span(),
+                (left, top),
+                desktop.borrow_bits(),
+            );
+        }
     });
 }
@@ -49,7 +49,25 @@
 //! but, column 6 does not.
 
 use std::mem;
-use crate::types::{Unit, Point, Dimension};
+use crate::types::{Unit, Point, Dimension, Rect};
+
+mod blit;
+pub use blit::RasterOp;
+
+mod color;
+pub use color::{ChunkyCanvas, Color, PixelFormat, Rgb565, Rgba8888};
+
+mod bmp;
+
+mod damage;
+pub use damage::coalesce_rects;
+
+mod fill;
+
+mod font;
+pub use font::Font;
+
+mod line;
 
 /// A pattern is an 8x8 pixel tile.
 pub type Pattern = [u8; 8];
@@ -87,6 +105,29 @@ pub trait Draw {
 
     /// Inverts a horizontal line.
     fn invert_horizontal_line(&mut self, left: Point, right: Unit);
+
+    /// Flood-fill the connected region of pixels matching the value of the pixel at `seed`,
+    /// replacing them with `set`.
+    ///
+    /// No-ops if `seed` falls outside the stencil, or if the pixel at `seed` already has the
+    /// value `set`.
+    fn fill(&mut self, seed: Point, set: bool);
+
+    /// Draw a line from `from` to `to` using Bresenham's algorithm, one pixel per step.
+    ///
+    /// `pattern` is consulted a bit at a time as the stroke walks from `from` to `to`: bit 7 is
+    /// consulted for the pixel at `from`, cycling every eight pixels. Unlike `vertical_line`, the
+    /// bit is chosen by the step count along the stroke rather than by the pixel's row. Pixels
+    /// that fall outside the stencil are skipped.
+    fn line(&mut self, from: Point, to: Point, pattern: u8);
+
+    /// Draw a filled ellipse centered at `center` with horizontal radius `rx` and vertical radius
+    /// `ry`, using the midpoint ellipse algorithm.
+    ///
+    /// Each scanline of the ellipse is drawn with `horizontal_line`, so it inherits that method's
+    /// clipping to the stencil; `pattern` is indexed the same way `filled_rectangle`'s is, by the
+    /// scanline's offset from the top of the ellipse's bounding box.
+    fn filled_ellipse(&mut self, center: Point, rx: Unit, ry: Unit, pattern: &Pattern);
 }
 
 /// A Stencil encapsulates a bitmapped image.
@@ -96,6 +137,9 @@ pub struct Stencil {
 
     /// The storage for the raw bits of the stencil.
     pub bits: Vec<u8>,
+
+    /// Rectangles touched by drawing operations since the last [`Stencil::take_damage`].
+    damage: Vec<Rect>,
 }
 
 static LEFT_MASKS: [u8; 8] = [ 0xFF, 0x7F, 0x3F, 0x1F, 0x0F, 0x07, 0x03, 0x01, ];
@@ -134,6 +178,7 @@ impl Stencil {
             Some(Self {
                 dimensions: (width as Dimension, height as Dimension),
                 bits,
+                damage: Vec::new(),
             })
         } else {
             None
@@ -334,6 +379,8 @@ impl Draw for Stencil {
 
             x = x + 1;
         }
+
+        self.mark_damage((left, top), (right + 1, top + 1));
     }
 
     fn framed_rectangle(&mut self, upper_left: Point, lower_right: Point, pattern: u8) {
@@ -378,6 +425,8 @@ impl Draw for Stencil {
             self.bits[y] = new_byte;
             y = y + stencil_span as usize;
         }
+
+        self.mark_damage((left, top), (left + 1, bottom));
     }
 
     fn invert_rectangle(&mut self, upper_left: Point, lower_right: Point) {
@@ -440,6 +489,20 @@ impl Draw for Stencil {
 
             x = x + 1;
         }
+
+        self.mark_damage((left, top), (right + 1, top + 1));
+    }
+
+    fn fill(&mut self, seed: Point, set: bool) {
+        fill::flood_fill(self, seed, set)
+    }
+
+    fn line(&mut self, from: Point, to: Point, pattern: u8) {
+        line::line(self, from, to, pattern)
+    }
+
+    fn filled_ellipse(&mut self, center: Point, rx: Unit, ry: Unit, pattern: &Pattern) {
+        line::filled_ellipse(self, center, rx, ry, pattern)
     }
 }
 
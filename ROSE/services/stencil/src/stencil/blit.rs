@@ -0,0 +1,214 @@
+//! Stencil-to-stencil bit block transfer (bitblt).
+
+use crate::types::{Point, Rect, Unit};
+
+use super::{canonize_rectangle, Stencil, LEFT_MASKS, RIGHT_MASKS};
+
+/// The combine mode used when blitting source bits onto a destination.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RasterOp {
+    /// Replace the destination bits with the source bits.
+    Copy,
+    /// OR the source bits into the destination.
+    Or,
+    /// AND the source bits into the destination.
+    And,
+    /// XOR the source bits into the destination.
+    Xor,
+    /// AND the destination with the complement of the source bits.
+    AndNot,
+}
+
+impl Stencil {
+    /// Copy the pixels of `src_rect` from `src` onto `self`, with the upper-left corner of the
+    /// copy landing at `dest`.
+    ///
+    /// `src_rect` is clipped to the dimensions of `src`, and the destination region is clipped to
+    /// `self.dimensions`, trimming the source region to match. The source and destination bits
+    /// need not share the same bit-within-byte alignment; unaligned source rows are reassembled a
+    /// byte at a time through a sliding 16-bit window.
+    pub fn blit(&mut self, src: &Stencil, src_rect: Rect, dest: Point, op: RasterOp) {
+        let (src_ul, src_lr) = canonize_rectangle(src_rect.0, src_rect.1);
+        let (mut src_left, mut src_top) = src_ul;
+        let (src_right, src_bottom) = src_lr;
+        let (src_width, src_height) = src.dimensions;
+
+        // Clip src_rect to the source stencil. When the left or top edge is off the source, the
+        // surviving pixels start further into the destination too, by however much got clamped.
+        let (mut dest_left, mut dest_top) = dest;
+
+        if src_left < 0 {
+            dest_left -= src_left;
+            src_left = 0;
+        }
+        if src_top < 0 {
+            dest_top -= src_top;
+            src_top = 0;
+        }
+        let src_right = src_right.min(src_width);
+        let src_bottom = src_bottom.min(src_height);
+
+        let mut width = src_right - src_left;
+        let mut height = src_bottom - src_top;
+        if (width <= 0) || (height <= 0) { return }
+
+        let (dest_width, dest_height) = self.dimensions;
+
+        // Clip the destination region to self.dimensions, trimming the source rectangle to
+        // follow suit.
+        if dest_left < 0 {
+            src_left -= dest_left;
+            width += dest_left;
+            dest_left = 0;
+        }
+        if dest_left + width > dest_width {
+            width = dest_width - dest_left;
+        }
+
+        if dest_top < 0 {
+            src_top -= dest_top;
+            height += dest_top;
+            dest_top = 0;
+        }
+        if dest_top + height > dest_height {
+            height = dest_height - dest_top;
+        }
+
+        if (width <= 0) || (height <= 0) { return }
+
+        for row in 0..height {
+            self.blit_row(src, (src_left, src_top + row), (dest_left, dest_top + row), width, op);
+        }
+
+        self.mark_damage((dest_left, dest_top), (dest_left + width, dest_top + height));
+    }
+
+    /// Blit a single row of `width` pixels from `src` at `src_pt` to `self` at `dest_pt`.
+    fn blit_row(&mut self, src: &Stencil, src_pt: Point, dest_pt: Point, width: Unit, op: RasterOp) {
+        let (src_left, src_y) = src_pt;
+        let (dest_left, dest_y) = dest_pt;
+
+        // The horizontal translation between the source and destination coordinate spaces is
+        // constant for the whole row, even though the two may not share bit-within-byte
+        // alignment.
+        let delta = dest_left - src_left;
+
+        let dest_span = self.get_span() as isize;
+        let src_span = src.get_span() as isize;
+        let dest_row_base = dest_span * (dest_y as isize);
+        let src_row_base = src_span * (src_y as isize);
+
+        let dest_right_incl = dest_left + width - 1;
+        let first_dest_byte = dest_left >> 3;
+        let last_dest_byte = dest_right_incl >> 3;
+        let left_mask = LEFT_MASKS[(dest_left & 7) as usize];
+        let right_mask = RIGHT_MASKS[(dest_right_incl & 7) as usize];
+
+        let mut byte_x = first_dest_byte;
+        while byte_x <= last_dest_byte {
+            // Source column aligned with the leftmost bit of this destination byte.
+            let src_col = (byte_x * 8) - delta;
+            let src_byte_idx = src_col.div_euclid(8) as isize;
+            let bit_shift = src_col.rem_euclid(8) as u32;
+
+            let b0 = Self::read_row_byte(src, src_row_base, src_span, src_byte_idx);
+            let b1 = Self::read_row_byte(src, src_row_base, src_span, src_byte_idx + 1);
+            let window = ((b0 as u16) << 8) | (b1 as u16);
+            let src_byte = (window.wrapping_shl(bit_shift) >> 8) as u8;
+
+            let mut mask = 0xFFu8;
+            if byte_x == first_dest_byte { mask &= left_mask; }
+            if byte_x == last_dest_byte { mask &= right_mask; }
+
+            let dest_idx = (dest_row_base + byte_x as isize) as usize;
+            let original = self.bits[dest_idx];
+            let combined = match op {
+                RasterOp::Copy => src_byte,
+                RasterOp::Or => original | src_byte,
+                RasterOp::And => original & src_byte,
+                RasterOp::Xor => original ^ src_byte,
+                RasterOp::AndNot => original & !src_byte,
+            };
+            self.bits[dest_idx] = (mask & combined) | (!mask & original);
+
+            byte_x += 1;
+        }
+    }
+
+    /// Read the byte at `byte_idx` within the row starting at `row_base`, answering 0 if the byte
+    /// falls outside the row (this is how bits beyond the left or right edge of the source
+    /// contribute nothing to the sliding window).
+    fn read_row_byte(src: &Stencil, row_base: isize, span: isize, byte_idx: isize) -> u8 {
+        if (byte_idx < 0) || (byte_idx >= span) {
+            0
+        } else {
+            src.bits[(row_base + byte_idx) as usize]
+        }
+    }
+}
+
+#[cfg(test)]
+mod blit_tests {
+    use super::*;
+
+    fn stencil_with_bits(width: Unit, height: Unit, bits: &[u8]) -> Stencil {
+        let mut stencil = Stencil::new_with_dimensions(width, height);
+        stencil.bits.copy_from_slice(bits);
+        stencil
+    }
+
+    #[test]
+    fn copy_aligned() {
+        let src = stencil_with_bits(8, 2, &[0xF0, 0xFF]);
+        let mut dest = Stencil::new_with_dimensions(8, 2);
+
+        dest.blit(&src, ((0, 0), (8, 2)), (0, 0), RasterOp::Copy);
+
+        assert_eq!(dest.bits, vec![0xF0, 0xFF]);
+    }
+
+    #[test]
+    fn copy_crosses_a_byte_boundary() {
+        // 16x1 source, left half set; blitting it 4 pixels to the right of an unaligned dest
+        // origin must reassemble the unaligned window rather than just copying src bytes as-is.
+        let src = stencil_with_bits(16, 1, &[0xFF, 0x00]);
+        let mut dest = Stencil::new_with_dimensions(24, 1);
+
+        dest.blit(&src, ((0, 0), (16, 1)), (4, 0), RasterOp::Copy);
+
+        assert_eq!(dest.bits, vec![0x0F, 0xF0, 0x00]);
+    }
+
+    #[test]
+    fn raster_ops_combine_with_the_destination() {
+        let src = stencil_with_bits(8, 1, &[0xFF]);
+
+        let mut or_dest = stencil_with_bits(8, 1, &[0x0F]);
+        or_dest.blit(&src, ((0, 0), (8, 1)), (0, 0), RasterOp::Or);
+        assert_eq!(or_dest.bits, vec![0xFF]);
+
+        let mut and_dest = stencil_with_bits(8, 1, &[0x0F]);
+        and_dest.blit(&src, ((0, 0), (8, 1)), (0, 0), RasterOp::And);
+        assert_eq!(and_dest.bits, vec![0x0F]);
+
+        let mut xor_dest = stencil_with_bits(8, 1, &[0x0F]);
+        xor_dest.blit(&src, ((0, 0), (8, 1)), (0, 0), RasterOp::Xor);
+        assert_eq!(xor_dest.bits, vec![0xF0]);
+
+        let mut and_not_dest = stencil_with_bits(8, 1, &[0xFF]);
+        and_not_dest.blit(&src, ((0, 0), (8, 1)), (0, 0), RasterOp::AndNot);
+        assert_eq!(and_not_dest.bits, vec![0x00]);
+    }
+
+    #[test]
+    fn src_rect_off_the_left_edge_shifts_dest_to_match() {
+        // src_rect starts 4 pixels before the source's left edge; the 4 surviving source columns
+        // must land 4 pixels into the destination, not at dest's own origin.
+        let src = stencil_with_bits(8, 1, &[0xFF]);
+        let mut dest = Stencil::new_with_dimensions(8, 1);
+
+        dest.blit(&src, ((-4, 0), (8, 1)), (0, 0), RasterOp::Copy);
+
+        assert_eq!(dest.bits, vec![0x0F]);
+    }
+}
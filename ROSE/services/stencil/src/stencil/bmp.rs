@@ -0,0 +1,185 @@
+//! Monochrome BMP import/export, so stencils can be authored in external tools.
+
+use crate::types::Dimension;
+
+use super::Stencil;
+
+const FILE_HEADER_SIZE: usize = 14;
+const INFO_HEADER_SIZE: usize = 40;
+
+impl Stencil {
+    /// Parse a monochrome (1-bpp, uncompressed) BMP image into a stencil.
+    ///
+    /// The BMP's 2-entry palette is consulted to determine which color index represents "ink":
+    /// whichever entry is darker is translated to a set bit, and the other to a clear bit. This
+    /// lets stencils round-trip through external pixel art tools regardless of how those tools
+    /// happen to order the palette.
+    ///
+    /// Answers `None` if `bytes` isn't a well-formed, uncompressed, 1-bpp BMP.
+    pub fn from_bmp(bytes: &[u8]) -> Option<Stencil> {
+        if bytes.len() < FILE_HEADER_SIZE + INFO_HEADER_SIZE { return None }
+        if &bytes[0..2] != b"BM" { return None }
+
+        let data_offset = u32::from_le_bytes(bytes[10..14].try_into().ok()?) as usize;
+
+        let info_header_size = u32::from_le_bytes(bytes[14..18].try_into().ok()?) as usize;
+        if info_header_size < INFO_HEADER_SIZE { return None }
+
+        let width = i32::from_le_bytes(bytes[18..22].try_into().ok()?);
+        let raw_height = i32::from_le_bytes(bytes[22..26].try_into().ok()?);
+        let planes = u16::from_le_bytes(bytes[26..28].try_into().ok()?);
+        let bit_count = u16::from_le_bytes(bytes[28..30].try_into().ok()?);
+        let compression = u32::from_le_bytes(bytes[30..34].try_into().ok()?);
+
+        // We only support the simplest case: one plane, one bit per pixel, no compression.
+        if (planes != 1) || (bit_count != 1) || (compression != 0) { return None }
+        if width <= 0 { return None }
+
+        let (top_down, height) = if raw_height < 0 { (true, -raw_height) } else { (false, raw_height) };
+        if height <= 0 { return None }
+
+        let width = width as Dimension;
+        let height = height as Dimension;
+
+        // Read the 2-entry palette, immediately following the info header.
+        let palette_offset = FILE_HEADER_SIZE + info_header_size;
+        if bytes.len() < palette_offset + 8 { return None }
+
+        let luminance = |entry: &[u8]| entry[0] as u32 + entry[1] as u32 + entry[2] as u32;
+        let entry0 = luminance(&bytes[palette_offset..palette_offset + 4]);
+        let entry1 = luminance(&bytes[palette_offset + 4..palette_offset + 8]);
+        let dark_index_is_zero = entry0 <= entry1;
+
+        let row_bytes = ((width as usize) + 7) / 8;
+        let row_padded = (row_bytes + 3) & !3;
+
+        if bytes.len() < data_offset + (row_padded * (height as usize)) { return None }
+
+        let mut stencil = Stencil::try_new_with_dimensions(width, height)?;
+        let dest_span = stencil.get_span();
+
+        for dest_row in 0..(height as usize) {
+            let src_row = if top_down { dest_row } else { (height as usize) - 1 - dest_row };
+            let src_start = data_offset + (src_row * row_padded);
+            let src = &bytes[src_start..src_start + row_bytes];
+
+            let dest_start = dest_row * dest_span;
+            for (i, &byte) in src.iter().enumerate() {
+                // A raw bit of 1 selects palette index 1; translate that into "set" only if
+                // index 1 is the darker of the two palette entries.
+                let translated = if dark_index_is_zero { !byte } else { byte };
+                stencil.bits[dest_start + i] = translated;
+            }
+        }
+
+        Some(stencil)
+    }
+
+    /// Emit `self` as a minimal, uncompressed, 1-bpp BMP with a black-and-white palette (index 0
+    /// is white/clear, index 1 is black/set).
+    pub fn to_bmp(&self) -> Vec<u8> {
+        let (width, height) = self.dimensions;
+        let src_span = self.get_span();
+        let row_bytes = ((width as usize) + 7) / 8;
+        let row_padded = (row_bytes + 3) & !3;
+        let pixel_data_size = row_padded * (height as usize);
+
+        let palette_size = 8; // 2 entries, 4 bytes each
+        let data_offset = FILE_HEADER_SIZE + INFO_HEADER_SIZE + palette_size;
+        let file_size = data_offset + pixel_data_size;
+
+        let mut out = Vec::with_capacity(file_size);
+
+        // BITMAPFILEHEADER
+        out.extend_from_slice(b"BM");
+        out.extend_from_slice(&(file_size as u32).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&(data_offset as u32).to_le_bytes());
+
+        // BITMAPINFOHEADER
+        out.extend_from_slice(&(INFO_HEADER_SIZE as u32).to_le_bytes());
+        out.extend_from_slice(&(width as i32).to_le_bytes());
+        out.extend_from_slice(&(height as i32).to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes()); // planes
+        out.extend_from_slice(&1u16.to_le_bytes()); // bit count
+        out.extend_from_slice(&0u32.to_le_bytes()); // compression (BI_RGB)
+        out.extend_from_slice(&(pixel_data_size as u32).to_le_bytes());
+        out.extend_from_slice(&0i32.to_le_bytes()); // x pixels per meter
+        out.extend_from_slice(&0i32.to_le_bytes()); // y pixels per meter
+        out.extend_from_slice(&0u32.to_le_bytes()); // colors used
+        out.extend_from_slice(&0u32.to_le_bytes()); // colors important
+
+        // Palette
+        out.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0x00]); // index 0: white
+        out.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // index 1: black
+
+        // Pixel data, bottom-up, 4-byte row padding.
+        let bits = self.borrow_bits();
+        for row in (0..(height as usize)).rev() {
+            let start = row * src_span;
+            out.extend_from_slice(&bits[start..start + row_bytes]);
+            out.resize(out.len() + (row_padded - row_bytes), 0);
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod bmp_tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_to_bmp_and_from_bmp() {
+        let mut stencil = Stencil::new_with_dimensions(10, 3);
+        stencil.bits.copy_from_slice(&[0xF0, 0x03, 0x0F, 0xC0, 0xAA, 0x80]);
+
+        let bytes = stencil.to_bmp();
+        let round_tripped = Stencil::from_bmp(&bytes).expect("well-formed BMP");
+
+        assert_eq!(round_tripped.dimensions, stencil.dimensions);
+        assert_eq!(round_tripped.bits, stencil.bits);
+    }
+
+    #[test]
+    fn translates_a_reversed_palette() {
+        // Same BMP `to_bmp` would emit for an 8x1 stencil with bits 0xAA, except the palette
+        // entries are swapped: index 0 is now black (darker), so a raw index-1 bit means "clear".
+        let mut bytes = Stencil::new_with_dimensions(8, 1).to_bmp();
+        let palette_offset = FILE_HEADER_SIZE + INFO_HEADER_SIZE;
+        bytes[palette_offset..palette_offset + 8].copy_from_slice(&[
+            0x00, 0x00, 0x00, 0x00, // index 0: black
+            0xFF, 0xFF, 0xFF, 0x00, // index 1: white
+        ]);
+        let pixel_data_offset = palette_offset + 8;
+        bytes[pixel_data_offset] = 0xAA;
+
+        let stencil = Stencil::from_bmp(&bytes).expect("well-formed BMP");
+
+        assert_eq!(stencil.bits, vec![!0xAAu8]);
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_1bpp() {
+        let mut bytes = Stencil::new_with_dimensions(8, 1).to_bmp();
+        bytes[28..30].copy_from_slice(&24u16.to_le_bytes()); // bit_count
+
+        assert!(Stencil::from_bmp(&bytes).is_none());
+    }
+
+    #[test]
+    fn rejects_compressed_files() {
+        let mut bytes = Stencil::new_with_dimensions(8, 1).to_bmp();
+        bytes[30..34].copy_from_slice(&1u32.to_le_bytes()); // compression (BI_RLE8)
+
+        assert!(Stencil::from_bmp(&bytes).is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let bytes = Stencil::new_with_dimensions(8, 1).to_bmp();
+
+        assert!(Stencil::from_bmp(&bytes[..FILE_HEADER_SIZE]).is_none());
+    }
+}
@@ -0,0 +1,225 @@
+//! Color surfaces that use stencils as paint masks.
+//!
+//! A [`Stencil`] is the crate's authoring format: a 1bpp bitmap, cheap to draw into and blit.
+//! A [`ChunkyCanvas`] is the compositing target: a color-capable backing store that a stencil can
+//! be painted through, one bit selecting between a foreground and an optional background color
+//! per pixel.
+
+use crate::types::{Dimension, Point, Unit};
+
+use super::Stencil;
+
+/// A 32-bit RGBA color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Construct an opaque color from its red, green, and blue components.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 0xFF }
+    }
+}
+
+/// A pixel format back end for [`ChunkyCanvas`], responsible for packing a [`Color`] into the
+/// canvas's native pixel representation.
+pub trait PixelFormat {
+    /// The number of bytes a single pixel occupies in the backing buffer.
+    const BYTES_PER_PIXEL: usize;
+
+    /// Write `color` into `bits`, starting at `offset`.
+    fn write_pixel(bits: &mut [u8], offset: usize, color: Color);
+}
+
+/// 16-bit, 5-6-5 packed color, big-endian.
+pub struct Rgb565;
+
+impl PixelFormat for Rgb565 {
+    const BYTES_PER_PIXEL: usize = 2;
+
+    fn write_pixel(bits: &mut [u8], offset: usize, color: Color) {
+        let r = (color.r >> 3) as u16;
+        let g = (color.g >> 2) as u16;
+        let b = (color.b >> 3) as u16;
+        let packed = (r << 11) | (g << 5) | b;
+
+        bits[offset] = (packed >> 8) as u8;
+        bits[offset + 1] = packed as u8;
+    }
+}
+
+/// 32-bit RGBA color, one byte per channel.
+pub struct Rgba8888;
+
+impl PixelFormat for Rgba8888 {
+    const BYTES_PER_PIXEL: usize = 4;
+
+    fn write_pixel(bits: &mut [u8], offset: usize, color: Color) {
+        bits[offset] = color.r;
+        bits[offset + 1] = color.g;
+        bits[offset + 2] = color.b;
+        bits[offset + 3] = color.a;
+    }
+}
+
+/// A color-capable compositing surface, backed by pixel format `F`.
+///
+/// Unlike a [`Stencil`], which stores 1 bit per pixel, a `ChunkyCanvas` stores one pixel's worth
+/// of color data contiguously ("chunky", as opposed to "planar") per pixel. Stencils remain the
+/// crate's authoring format; a `ChunkyCanvas` is where they get painted for display on
+/// color-capable hardware.
+pub struct ChunkyCanvas<F: PixelFormat> {
+    /// (Width, Height) of the canvas, in dots.
+    pub dimensions: (Dimension, Dimension),
+
+    /// The storage for the raw bytes of the canvas.
+    pub bits: Vec<u8>,
+
+    _format: std::marker::PhantomData<F>,
+}
+
+impl<F: PixelFormat> ChunkyCanvas<F> {
+    /// Create a new canvas with the dimensions (width, height) provided.
+    /// If the dimensions are inappropriate (e.g., a width which would overflow a signed integer),
+    /// or if insufficient memory is available to hold the canvas,
+    /// panic.
+    pub fn new_with_dimensions(width: Dimension, height: Dimension) -> Self {
+        Self::try_new_with_dimensions(width, height).expect("ChunkyCanvas creation failure")
+    }
+
+    /// Create a new canvas with the dimensions (width, height) provided.
+    /// If the dimensions are inappropriate, answer with None.  Otherwise, yield a canvas.
+    pub fn try_new_with_dimensions(width: Dimension, height: Dimension) -> Option<Self> {
+        if (width > 0) && (height > 0) {
+            let span = (width as usize) * F::BYTES_PER_PIXEL;
+            let size = span * (height as usize);
+
+            let mut bits = Vec::with_capacity(size);
+            bits.resize(size, 0);
+
+            Some(Self {
+                dimensions: (width as Dimension, height as Dimension),
+                bits,
+                _format: std::marker::PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Answer with the number of bytes a single row of pixels takes in memory.
+    pub fn get_span(&self) -> usize {
+        (self.dimensions.0 as usize) * F::BYTES_PER_PIXEL
+    }
+
+    /// Borrow the buffer containing the canvas as a slice of bytes.
+    pub fn borrow_bits(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// Paint `fg` onto `self` wherever `mask`'s bit is set, with `mask`'s upper-left corner placed
+    /// at `mask_origin`. Where the mask's bit is clear, write `bg` if supplied; otherwise leave
+    /// the destination pixel untouched.
+    ///
+    /// Pixels of `mask` that fall outside `self` are clipped away.
+    pub fn paint_through(&mut self, mask: &Stencil, mask_origin: Point, fg: Color, bg: Option<Color>) {
+        let (mask_width, mask_height) = mask.dimensions;
+        let mask_span = mask.get_span();
+        let mask_bits = mask.borrow_bits();
+        let (canvas_width, canvas_height) = self.dimensions;
+        let (origin_x, origin_y) = mask_origin;
+
+        for mask_y in 0..mask_height {
+            let canvas_y = origin_y + mask_y;
+            if (canvas_y < 0) || (canvas_y >= canvas_height) { continue }
+
+            let row_base = mask_span * (mask_y as usize);
+
+            for mask_x in 0..mask_width {
+                let canvas_x = origin_x + mask_x;
+                if (canvas_x < 0) || (canvas_x >= canvas_width) { continue }
+
+                let byte = mask_bits[row_base + ((mask_x as usize) >> 3)];
+                let bit = 0x80u8 >> (mask_x & 7);
+
+                if (byte & bit) != 0 {
+                    self.write_pixel(canvas_x, canvas_y, fg);
+                } else if let Some(bg) = bg {
+                    self.write_pixel(canvas_x, canvas_y, bg);
+                }
+            }
+        }
+    }
+
+    /// Write a single pixel's worth of color at `(x, y)`.  Callers must ensure the coordinate
+    /// falls within `self.dimensions`.
+    fn write_pixel(&mut self, x: Unit, y: Unit, color: Color) {
+        let span = self.get_span();
+        let offset = (span * (y as usize)) + ((x as usize) * F::BYTES_PER_PIXEL);
+
+        F::write_pixel(&mut self.bits, offset, color);
+    }
+}
+
+#[cfg(test)]
+mod paint_through_tests {
+    use super::*;
+
+    fn mask_with_bits(width: Dimension, height: Dimension, bits: &[u8]) -> Stencil {
+        let mut mask = Stencil::new_with_dimensions(width, height);
+        mask.bits.copy_from_slice(bits);
+        mask
+    }
+
+    #[test]
+    fn rgb565_packs_fg_where_the_mask_bit_is_set() {
+        let mask = mask_with_bits(8, 1, &[0b1010_0000]);
+        let mut canvas = ChunkyCanvas::<Rgb565>::new_with_dimensions(8, 1);
+
+        canvas.paint_through(&mask, (0, 0), Color::rgb(0xFF, 0xFF, 0xFF), None);
+
+        // Pixel 0 and 2 are painted white (0xFFFF), the rest left untouched (zero).
+        assert_eq!(&canvas.bits[0..2], &[0xFF, 0xFF]);
+        assert_eq!(&canvas.bits[2..4], &[0x00, 0x00]);
+        assert_eq!(&canvas.bits[4..6], &[0xFF, 0xFF]);
+        assert_eq!(&canvas.bits[6..8], &[0x00, 0x00]);
+    }
+
+    #[test]
+    fn rgba8888_writes_bg_where_the_mask_bit_is_clear() {
+        let mask = mask_with_bits(8, 1, &[0b1000_0000]);
+        let mut canvas = ChunkyCanvas::<Rgba8888>::new_with_dimensions(8, 1);
+        let fg = Color::rgb(0x11, 0x22, 0x33);
+        let bg = Color::rgb(0x44, 0x55, 0x66);
+
+        canvas.paint_through(&mask, (0, 0), fg, Some(bg));
+
+        assert_eq!(&canvas.bits[0..4], &[0x11, 0x22, 0x33, 0xFF]);
+        assert_eq!(&canvas.bits[4..8], &[0x44, 0x55, 0x66, 0xFF]);
+    }
+
+    #[test]
+    fn leaves_destination_untouched_where_the_mask_is_clear_and_no_bg_given() {
+        let mask = mask_with_bits(8, 1, &[0b1000_0000]);
+        let mut canvas = ChunkyCanvas::<Rgba8888>::new_with_dimensions(8, 1);
+        canvas.bits[4..8].copy_from_slice(&[0xAA, 0xBB, 0xCC, 0xDD]);
+
+        canvas.paint_through(&mask, (0, 0), Color::rgb(0x11, 0x22, 0x33), None);
+
+        assert_eq!(&canvas.bits[4..8], &[0xAA, 0xBB, 0xCC, 0xDD]);
+    }
+
+    #[test]
+    fn clips_mask_pixels_outside_the_canvas() {
+        let mask = mask_with_bits(8, 1, &[0xFF]);
+        let mut canvas = ChunkyCanvas::<Rgb565>::new_with_dimensions(4, 1);
+
+        canvas.paint_through(&mask, (0, 0), Color::rgb(0xFF, 0xFF, 0xFF), None);
+
+        assert_eq!(canvas.bits, vec![0xFF; 8]);
+    }
+}
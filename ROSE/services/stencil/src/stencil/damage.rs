@@ -0,0 +1,104 @@
+//! Dirty-rectangle tracking, so incremental UIs can redraw only what changed instead of
+//! re-uploading the whole stencil on every tick.
+
+use std::mem;
+
+use crate::types::{Point, Rect};
+
+use super::Stencil;
+
+impl Stencil {
+    /// Drain and answer the rectangles touched by drawing operations since the last call to
+    /// `take_damage` (or since the stencil was created, on the first call).
+    ///
+    /// Rectangles are recorded as drawing operations clip them to `self.dimensions`; they are not
+    /// merged or deduplicated here, since whether that's worthwhile depends on what the caller
+    /// intends to do with the result.
+    pub fn take_damage(&mut self) -> Vec<Rect> {
+        mem::take(&mut self.damage)
+    }
+
+    /// Record that `upper_left`..`lower_right` was touched by a drawing operation.
+    ///
+    /// The rectangle is clipped to `self.dimensions` first; no-ops if the clipped rectangle is
+    /// empty.
+    pub(crate) fn mark_damage(&mut self, upper_left: Point, lower_right: Point) {
+        let (width, height) = self.dimensions;
+        let (left, top) = upper_left;
+        let (right, bottom) = lower_right;
+
+        let left = left.max(0);
+        let top = top.max(0);
+        let right = right.min(width);
+        let bottom = bottom.min(height);
+
+        if (right > left) && (bottom > top) {
+            self.damage.push(((left, top), (right, bottom)));
+        }
+    }
+}
+
+/// Merge overlapping or nearly-overlapping rectangles in `rects`, so a host presenting a batch of
+/// damage (e.g. from [`Stencil::take_damage`]) spends less time on redundant, overlapping regions.
+///
+/// Two rectangles are merged whenever their union's area is at most 1.5x the sum of their
+/// individual areas, even if they don't strictly overlap; this catches adjacent damage, such as
+/// the individual scanlines of a `filled_rectangle`, without merging distant rectangles together.
+pub fn coalesce_rects(rects: Vec<Rect>) -> Vec<Rect> {
+    let mut merged: Vec<Rect> = Vec::new();
+
+    for mut rect in rects {
+        // Keep absorbing existing rectangles until the grown rectangle merges with none of them.
+        while let Some(i) = merged.iter().position(|existing| should_merge(*existing, rect)) {
+            rect = union(merged.swap_remove(i), rect);
+        }
+
+        merged.push(rect);
+    }
+
+    merged
+}
+
+/// Answer whether `a` and `b` are worth merging into a single damage rectangle, per the area
+/// heuristic documented on [`coalesce_rects`].
+fn should_merge(a: Rect, b: Rect) -> bool {
+    let union_area = area(union(a, b));
+    let sum_area = area(a) + area(b);
+
+    (union_area * 2) <= (sum_area * 3)
+}
+
+/// The area of `rect`, in square dots.
+fn area(rect: Rect) -> i64 {
+    let ((left, top), (right, bottom)) = rect;
+
+    (right - left) as i64 * (bottom - top) as i64
+}
+
+/// The smallest rectangle enclosing both `a` and `b`.
+fn union(a: Rect, b: Rect) -> Rect {
+    let ((a_left, a_top), (a_right, a_bottom)) = a;
+    let ((b_left, b_top), (b_right, b_bottom)) = b;
+
+    (
+        (a_left.min(b_left), a_top.min(b_top)),
+        (a_right.max(b_right), a_bottom.max(b_bottom)),
+    )
+}
+
+#[cfg(test)]
+mod coalesce_rects_tests {
+    use super::coalesce_rects;
+
+    #[test]
+    fn merges_overlapping_rects() {
+        let rects = vec![((0, 0), (10, 10)), ((5, 5), (15, 15))];
+        assert_eq!(coalesce_rects(rects), vec![((0, 0), (15, 15))]);
+    }
+
+    #[test]
+    fn leaves_distant_rects_unmerged() {
+        let rects = vec![((0, 0), (10, 10)), ((1000, 1000), (1010, 1010))];
+        assert_eq!(coalesce_rects(rects), vec![((0, 0), (10, 10)), ((1000, 1000), (1010, 1010))]);
+    }
+}
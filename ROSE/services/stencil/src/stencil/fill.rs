@@ -0,0 +1,122 @@
+//! Scanline flood fill.
+
+use crate::types::{Point, Unit};
+
+use super::{Draw, Stencil};
+
+/// Flood-fill the connected region of pixels matching the seed pixel's current value, replacing
+/// them with `set`, using the efficient scanline variant of the algorithm: an explicit stack of
+/// seed points, each expanded into the maximal same-valued run on its row before seeding the rows
+/// above and below.
+pub(crate) fn flood_fill(stencil: &mut Stencil, seed: Point, set: bool) {
+    let (width, height) = stencil.dimensions;
+    let (seed_x, seed_y) = seed;
+
+    if (seed_x < 0) || (seed_x >= width) || (seed_y < 0) || (seed_y >= height) { return }
+
+    let span = stencil.get_span();
+    let target = get_pixel(stencil, span, seed_x, seed_y);
+    if target == set { return }
+
+    let mut stack = vec![(seed_x, seed_y)];
+
+    while let Some((x, y)) = stack.pop() {
+        if (x < 0) || (x >= width) || (y < 0) || (y >= height) { continue }
+        if get_pixel(stencil, span, x, y) != target { continue }
+
+        // Scan left and right from the seed along the row to find the maximal run of
+        // same-valued pixels.
+        let mut left = x;
+        while (left > 0) && (get_pixel(stencil, span, left - 1, y) == target) {
+            left -= 1;
+        }
+
+        let mut right = x;
+        while (right + 1 < width) && (get_pixel(stencil, span, right + 1, y) == target) {
+            right += 1;
+        }
+
+        // Fill the run with a masked horizontal_line-style write.
+        stencil.horizontal_line((left, y), right + 1, if set { 0xFF } else { 0x00 });
+
+        // Scan the rows immediately above and below the filled span, pushing one seed per
+        // newly discovered unfilled run.
+        for neighbor_y in [y - 1, y + 1] {
+            if (neighbor_y < 0) || (neighbor_y >= height) { continue }
+
+            let mut x = left;
+            while x <= right {
+                if get_pixel(stencil, span, x, neighbor_y) == target {
+                    stack.push((x, neighbor_y));
+
+                    while (x <= right) && (get_pixel(stencil, span, x, neighbor_y) == target) {
+                        x += 1;
+                    }
+                } else {
+                    x += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Read the value of the pixel at `(x, y)`, which must already be known to fall within bounds.
+fn get_pixel(stencil: &Stencil, span: usize, x: Unit, y: Unit) -> bool {
+    let idx = (span * (y as usize)) + ((x as usize) >> 3);
+    let mask = 0x80u8 >> (x & 7);
+
+    (stencil.bits[idx] & mask) != 0
+}
+
+#[cfg(test)]
+mod flood_fill_tests {
+    use super::*;
+
+    #[test]
+    fn fills_a_bounded_region() {
+        // A 3x3 clear box (rows/cols 1..4) inside an otherwise set 5x5 stencil.
+        let mut stencil = Stencil::new_with_dimensions(5, 5);
+        stencil.bits.fill(0xFF);
+        for y in 1..4 {
+            stencil.invert_horizontal_line((1, y), 4);
+        }
+
+        stencil.fill((2, 2), true);
+
+        assert_eq!(stencil.bits, vec![0xFF; 5]);
+    }
+
+    #[test]
+    fn does_not_spill_into_a_disconnected_matching_region() {
+        // Two isolated clear pixels in an otherwise set row, far enough apart to not be
+        // connected. Filling one must leave the other alone.
+        let mut stencil = Stencil::new_with_dimensions(9, 1);
+        stencil.bits.fill(0xFF);
+        stencil.invert_horizontal_line((1, 0), 2);
+        stencil.invert_horizontal_line((7, 0), 8);
+
+        stencil.fill((1, 0), true);
+
+        let span = stencil.get_span();
+        assert!(get_pixel(&stencil, span, 1, 0));
+        assert!(!get_pixel(&stencil, span, 7, 0));
+    }
+
+    #[test]
+    fn noop_when_seed_already_matches_target() {
+        let mut stencil = Stencil::new_with_dimensions(4, 4);
+
+        stencil.fill((0, 0), false);
+
+        assert_eq!(stencil.bits, vec![0x00; 4]);
+    }
+
+    #[test]
+    fn noop_when_seed_is_out_of_bounds() {
+        let mut stencil = Stencil::new_with_dimensions(4, 4);
+
+        stencil.fill((10, 10), true);
+
+        assert_eq!(stencil.bits, vec![0x00; 4]);
+    }
+}
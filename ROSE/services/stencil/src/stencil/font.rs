@@ -0,0 +1,219 @@
+//! Bitmap fonts and text rendering into stencils.
+
+use std::collections::HashMap;
+
+use crate::types::{Point, Rect, Unit};
+
+use super::{Draw, RasterOp, Stencil};
+
+/// A single pixel's worth of "ink", used when stamping glyph artwork into the font atlas.
+const INK: [u8; 8] = [0xFF; 8];
+
+/// Where a single glyph lives within a [`Font`]'s atlas, and how far the pen should advance past
+/// it.
+struct Glyph {
+    atlas_x: Unit,
+    width: Unit,
+}
+
+/// A bitmap font: a [`Stencil`] atlas holding every glyph's artwork side by side, plus a
+/// per-glyph x-offset/width table and a baseline, so proportional as well as fixed-width fonts
+/// can be represented.
+pub struct Font {
+    /// The glyph atlas. Every glyph is `glyph_height` pixels tall and occupies some horizontal
+    /// span of the atlas, recorded in `glyphs`.
+    pub atlas: Stencil,
+
+    /// Height of every glyph's bounding box, in pixels.
+    pub glyph_height: Unit,
+
+    /// Offset from the top of a glyph's bounding box down to its baseline.
+    pub baseline: Unit,
+
+    glyphs: HashMap<char, Glyph>,
+    missing_glyph: Glyph,
+}
+
+impl Font {
+    /// The crate's built-in 6x8 ASCII font: space, digits, a colon, and uppercase letters.
+    /// Characters outside that set render as a hollow "missing glyph" box.
+    pub fn builtin_6x8() -> Font {
+        let rows: Vec<(char, [&str; 7])> = vec![
+            (' ', BLANK),
+            ('0', DIGIT_0), ('1', DIGIT_1), ('2', DIGIT_2), ('3', DIGIT_3), ('4', DIGIT_4),
+            ('5', DIGIT_5), ('6', DIGIT_6), ('7', DIGIT_7), ('8', DIGIT_8), ('9', DIGIT_9),
+            (':', COLON),
+            ('A', LETTER_A), ('B', LETTER_B), ('C', LETTER_C), ('D', LETTER_D), ('E', LETTER_E),
+            ('F', LETTER_F), ('G', LETTER_G), ('H', LETTER_H), ('I', LETTER_I), ('J', LETTER_J),
+            ('K', LETTER_K), ('L', LETTER_L), ('M', LETTER_M), ('N', LETTER_N), ('O', LETTER_O),
+            ('P', LETTER_P), ('Q', LETTER_Q), ('R', LETTER_R), ('S', LETTER_S), ('T', LETTER_T),
+            ('U', LETTER_U), ('V', LETTER_V), ('W', LETTER_W), ('X', LETTER_X), ('Y', LETTER_Y),
+            ('Z', LETTER_Z),
+        ];
+
+        const GLYPH_WIDTH: Unit = 6;
+        const GLYPH_HEIGHT: Unit = 8;
+
+        let atlas_width = GLYPH_WIDTH * (rows.len() as Unit + 1); // +1 for the missing-glyph box
+        let mut atlas = Stencil::new_with_dimensions(atlas_width, GLYPH_HEIGHT);
+
+        let mut glyphs = HashMap::with_capacity(rows.len());
+        let mut atlas_x = 0;
+        for (ch, glyph_rows) in &rows {
+            stamp_glyph(&mut atlas, atlas_x, glyph_rows);
+            glyphs.insert(*ch, Glyph { atlas_x, width: GLYPH_WIDTH });
+            atlas_x += GLYPH_WIDTH;
+        }
+
+        // The missing-glyph box: a hollow rectangle, drawn for any character we have no artwork
+        // for.
+        atlas.framed_rectangle((atlas_x + 1, 1), (atlas_x + GLYPH_WIDTH - 1, GLYPH_HEIGHT - 1), 0xFF);
+        let missing_glyph = Glyph { atlas_x, width: GLYPH_WIDTH };
+
+        Font { atlas, glyph_height: GLYPH_HEIGHT, baseline: GLYPH_HEIGHT - 1, glyphs, missing_glyph }
+    }
+
+    fn glyph_for(&self, ch: char) -> &Glyph {
+        self.glyphs.get(&ch).unwrap_or(&self.missing_glyph)
+    }
+}
+
+/// Stamp one glyph's artwork, described as seven rows of `'#'`/`.` characters, into `atlas` at
+/// `atlas_x`.
+fn stamp_glyph(atlas: &mut Stencil, atlas_x: Unit, glyph_rows: &[&str; 7]) {
+    for (row, line) in glyph_rows.iter().enumerate() {
+        for (col, mark) in line.chars().enumerate() {
+            if mark == '#' {
+                let x = atlas_x + col as Unit;
+                let y = row as Unit;
+                atlas.filled_rectangle((x, y), (x + 1, y + 1), &INK);
+            }
+        }
+    }
+}
+
+impl Stencil {
+    /// Composite `s` into `self`, starting at `origin`, using `font`'s glyph artwork.  Glyphs
+    /// that cross the stencil's edges are clipped as usual.  Answers the pen position
+    /// immediately past the last glyph drawn, so callers can lay out further runs of text.
+    pub fn draw_text(&mut self, font: &Font, origin: Point, s: &str) -> Point {
+        let (mut pen_x, pen_y) = origin;
+
+        for ch in s.chars() {
+            let glyph = font.glyph_for(ch);
+            let src_rect: Rect = (
+                (glyph.atlas_x, 0),
+                (glyph.atlas_x + glyph.width, font.glyph_height),
+            );
+
+            self.blit(&font.atlas, src_rect, (pen_x, pen_y), RasterOp::Or);
+            pen_x += glyph.width;
+        }
+
+        (pen_x, pen_y)
+    }
+}
+
+type GlyphRows = [&'static str; 7];
+
+const BLANK: GlyphRows =    [".....", ".....", ".....", ".....", ".....", ".....", "....."];
+
+const DIGIT_0: GlyphRows =  [".###.", "#...#", "#..##", "#.#.#", "##..#", "#...#", ".###."];
+const DIGIT_1: GlyphRows =  ["..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###."];
+const DIGIT_2: GlyphRows =  [".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####"];
+const DIGIT_3: GlyphRows =  [".###.", "#...#", "....#", "..##.", "....#", "#...#", ".###."];
+const DIGIT_4: GlyphRows =  ["...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#."];
+const DIGIT_5: GlyphRows =  ["#####", "#....", "####.", "....#", "....#", "#...#", ".###."];
+const DIGIT_6: GlyphRows =  ["..##.", ".#...", "#....", "####.", "#...#", "#...#", ".###."];
+const DIGIT_7: GlyphRows =  ["#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#..."];
+const DIGIT_8: GlyphRows =  [".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###."];
+const DIGIT_9: GlyphRows =  [".###.", "#...#", "#...#", ".####", "....#", "...#.", ".##.."];
+
+const COLON: GlyphRows =    [".....", "..#..", "..#..", ".....", "..#..", "..#..", "....."];
+
+const LETTER_A: GlyphRows = [".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"];
+const LETTER_B: GlyphRows = ["####.", "#...#", "#...#", "####.", "#...#", "#...#", "####."];
+const LETTER_C: GlyphRows = [".###.", "#...#", "#....", "#....", "#....", "#...#", ".###."];
+const LETTER_D: GlyphRows = ["####.", "#...#", "#...#", "#...#", "#...#", "#...#", "####."];
+const LETTER_E: GlyphRows = ["#####", "#....", "#....", "####.", "#....", "#....", "#####"];
+const LETTER_F: GlyphRows = ["#####", "#....", "#....", "####.", "#....", "#....", "#...."];
+const LETTER_G: GlyphRows = [".###.", "#...#", "#....", "#.###", "#...#", "#...#", ".###."];
+const LETTER_H: GlyphRows = ["#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"];
+const LETTER_I: GlyphRows = ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "#####"];
+const LETTER_J: GlyphRows = ["..###", "...#.", "...#.", "...#.", "...#.", "#..#.", ".##.."];
+const LETTER_K: GlyphRows = ["#...#", "#..#.", "#.#..", "##...", "#.#..", "#..#.", "#...#"];
+const LETTER_L: GlyphRows = ["#....", "#....", "#....", "#....", "#....", "#....", "#####"];
+const LETTER_M: GlyphRows = ["#...#", "##.##", "#.#.#", "#...#", "#...#", "#...#", "#...#"];
+const LETTER_N: GlyphRows = ["#...#", "##..#", "#.#.#", "#..##", "#...#", "#...#", "#...#"];
+const LETTER_O: GlyphRows = [".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."];
+const LETTER_P: GlyphRows = ["####.", "#...#", "#...#", "####.", "#....", "#....", "#...."];
+const LETTER_Q: GlyphRows = [".###.", "#...#", "#...#", "#...#", "#.#.#", "#..#.", ".##.#"];
+const LETTER_R: GlyphRows = ["####.", "#...#", "#...#", "####.", "#.#..", "#..#.", "#...#"];
+const LETTER_S: GlyphRows = [".####", "#....", "#....", ".###.", "....#", "....#", "####."];
+const LETTER_T: GlyphRows = ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "..#.."];
+const LETTER_U: GlyphRows = ["#...#", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."];
+const LETTER_V: GlyphRows = ["#...#", "#...#", "#...#", "#...#", "#...#", ".#.#.", "..#.."];
+const LETTER_W: GlyphRows = ["#...#", "#...#", "#...#", "#.#.#", "#.#.#", "##.##", "#...#"];
+const LETTER_X: GlyphRows = ["#...#", "#...#", ".#.#.", "..#..", ".#.#.", "#...#", "#...#"];
+const LETTER_Y: GlyphRows = ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#..", "..#.."];
+const LETTER_Z: GlyphRows = ["#####", "....#", "...#.", "..#..", ".#...", "#....", "#####"];
+
+#[cfg(test)]
+mod font_tests {
+    use super::*;
+
+    #[test]
+    fn advances_the_pen_by_each_glyphs_width() {
+        let font = Font::builtin_6x8();
+
+        let mut stencil = Stencil::new_with_dimensions(40, 8);
+        let pen = stencil.draw_text(&font, (0, 0), "12");
+
+        assert_eq!(pen, (12, 0));
+    }
+
+    #[test]
+    fn draws_ink_for_a_known_glyph() {
+        let font = Font::builtin_6x8();
+        let mut stencil = Stencil::new_with_dimensions(6, 8);
+
+        stencil.draw_text(&font, (0, 0), "1");
+
+        let expected_row = |row: &str| {
+            row.chars().enumerate().fold(0u8, |acc, (col, mark)| {
+                if mark == '#' { acc | (0x80 >> col) } else { acc }
+            })
+        };
+        assert_eq!(stencil.bits[0], expected_row(DIGIT_1[0]));
+        assert_eq!(stencil.bits[1], expected_row(DIGIT_1[1]));
+        assert_eq!(stencil.bits[7], 0x00); // row 7 is past the 7-row glyph art, always blank
+    }
+
+    #[test]
+    fn unknown_characters_render_as_the_missing_glyph() {
+        let font = Font::builtin_6x8();
+        let mut tilde = Stencil::new_with_dimensions(6, 8);
+        let mut bang = Stencil::new_with_dimensions(6, 8);
+        let mut missing = Stencil::new_with_dimensions(6, 8);
+
+        tilde.draw_text(&font, (0, 0), "~");
+        bang.draw_text(&font, (0, 0), "!");
+        let glyph = &font.missing_glyph;
+        let src_rect = ((glyph.atlas_x, 0), (glyph.atlas_x + glyph.width, font.glyph_height));
+        missing.blit(&font.atlas, src_rect, (0, 0), RasterOp::Or);
+
+        assert_eq!(tilde.bits, missing.bits);
+        assert_eq!(bang.bits, missing.bits);
+    }
+
+    #[test]
+    fn clips_glyphs_crossing_the_stencil_edge() {
+        let font = Font::builtin_6x8();
+        let mut stencil = Stencil::new_with_dimensions(3, 8);
+
+        // Should clip rather than panic; only the left half of "1" fits.
+        let pen = stencil.draw_text(&font, (0, 0), "1");
+
+        assert_eq!(pen, (6, 0));
+    }
+}
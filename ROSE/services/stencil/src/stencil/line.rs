@@ -0,0 +1,227 @@
+//! Freehand line and ellipse drawing, for brush strokes that aren't axis-aligned.
+
+use crate::types::{Point, Unit};
+
+use super::{Draw, Pattern, Stencil};
+
+/// Draw a line from `from` to `to` using Bresenham's algorithm, one pixel per step. See
+/// [`super::Draw::line`] for how `pattern` is consulted.
+pub(crate) fn line(stencil: &mut Stencil, from: Point, to: Point, pattern: u8) {
+    let (mut x, mut y) = from;
+    let (x1, y1) = to;
+
+    let dx = (x1 - x).abs();
+    let dy = (y1 - y).abs();
+    let sx: Unit = if x < x1 { 1 } else { -1 };
+    let sy: Unit = if y < y1 { 1 } else { -1 };
+    let mut err = dx - dy;
+
+    let mut step: Unit = 0;
+    loop {
+        let pattern_mask = 0x80u8 >> (step & 7);
+        set_pixel(stencil, x, y, (pattern & pattern_mask) != 0);
+        step += 1;
+
+        if (x == x1) && (y == y1) { break }
+
+        let e2 = 2 * err;
+        if e2 > -dy {
+            err -= dy;
+            x += sx;
+        }
+        if e2 < dx {
+            err += dx;
+            y += sy;
+        }
+    }
+
+    let (left, top) = (from.0.min(to.0), from.1.min(to.1));
+    let (right, bottom) = (from.0.max(to.0) + 1, from.1.max(to.1) + 1);
+    stencil.mark_damage((left, top), (right, bottom));
+}
+
+/// Set or clear the pixel at `(x, y)`, no-op if it falls outside `stencil.dimensions`.
+fn set_pixel(stencil: &mut Stencil, x: Unit, y: Unit, value: bool) {
+    let (width, height) = stencil.dimensions;
+    if (x < 0) || (x >= width) || (y < 0) || (y >= height) { return }
+
+    let span = stencil.get_span();
+    let idx = (span * (y as usize)) + ((x as usize) >> 3);
+    let mask = 0x80u8 >> (x & 7);
+
+    if value {
+        stencil.bits[idx] |= mask;
+    } else {
+        stencil.bits[idx] &= !mask;
+    }
+}
+
+/// Draw a filled ellipse centered at `center` with radii `rx` and `ry`, using the midpoint
+/// ellipse algorithm. See [`super::Draw::filled_ellipse`] for how `pattern` is consulted.
+pub(crate) fn filled_ellipse(stencil: &mut Stencil, center: Point, rx: Unit, ry: Unit, pattern: &Pattern) {
+    if (rx <= 0) || (ry <= 0) { return }
+
+    let (cx, cy) = center;
+    let rx2 = (rx as i64) * (rx as i64);
+    let ry2 = (ry as i64) * (ry as i64);
+
+    let mut x: i64 = 0;
+    let mut y: i64 = ry as i64;
+
+    draw_scanline_pair(stencil, cx, cy, ry, x as Unit, y as Unit, pattern);
+
+    // Region 1: the boundary's slope is shallower than -1, so x is stepped every iteration and y
+    // drops only when the midpoint says the boundary has passed it.
+    //
+    // d1 is tracked scaled by 4 so its 0.25*rx^2 term stays an exact integer rather than
+    // truncating.
+    let mut dx = 2 * ry2 * x;
+    let mut dy = 2 * rx2 * y;
+    let mut d1 = (4 * ry2) - (4 * rx2 * y) + rx2;
+
+    while dx < dy {
+        x += 1;
+        dx += 2 * ry2;
+
+        if d1 < 0 {
+            d1 += (4 * dx) + (4 * ry2);
+        } else {
+            y -= 1;
+            dy -= 2 * rx2;
+            d1 += (4 * dx) - (4 * dy) + (4 * ry2);
+        }
+
+        draw_scanline_pair(stencil, cx, cy, ry, x as Unit, y as Unit, pattern);
+    }
+
+    // Region 2: the boundary's slope is steeper than -1, so y is stepped every iteration and x
+    // advances only when the midpoint says the boundary has passed it. d2 is likewise tracked
+    // scaled by 4.
+    let mut d2 = (ry2 * (2 * x + 1) * (2 * x + 1)) + (4 * rx2 * (y - 1) * (y - 1)) - (4 * rx2 * ry2);
+
+    while y > 0 {
+        y -= 1;
+        dy -= 2 * rx2;
+
+        if d2 > 0 {
+            d2 += (4 * rx2) - (4 * dy);
+        } else {
+            x += 1;
+            dx += 2 * ry2;
+            d2 += (4 * dx) - (4 * dy) + (4 * rx2);
+        }
+
+        draw_scanline_pair(stencil, cx, cy, ry, x as Unit, y as Unit, pattern);
+    }
+
+    let (left, top) = (cx - rx, cy - ry);
+    stencil.mark_damage((left, top), (left + (2 * rx) + 1, top + (2 * ry) + 1));
+}
+
+#[cfg(test)]
+mod line_tests {
+    use super::*;
+
+    fn get_pixel(stencil: &Stencil, x: Unit, y: Unit) -> bool {
+        let span = stencil.get_span();
+        let idx = (span * (y as usize)) + ((x as usize) >> 3);
+        let mask = 0x80u8 >> (x & 7);
+
+        (stencil.bits[idx] & mask) != 0
+    }
+
+    #[test]
+    fn draws_both_endpoints() {
+        let mut stencil = Stencil::new_with_dimensions(10, 10);
+
+        stencil.line((1, 1), (8, 1), 0xFF);
+
+        assert!(get_pixel(&stencil, 1, 1));
+        assert!(get_pixel(&stencil, 8, 1));
+    }
+
+    #[test]
+    fn steep_slope_steps_one_pixel_per_row() {
+        let mut stencil = Stencil::new_with_dimensions(10, 10);
+
+        stencil.line((2, 0), (2, 9), 0xFF);
+
+        for y in 0..10 {
+            assert!(get_pixel(&stencil, 2, y), "expected column 2 set at row {y}");
+        }
+    }
+
+    #[test]
+    fn diagonal_line_visits_every_row_and_column_once() {
+        let mut stencil = Stencil::new_with_dimensions(10, 10);
+
+        stencil.line((0, 0), (9, 9), 0xFF);
+
+        for i in 0..10 {
+            assert!(get_pixel(&stencil, i, i));
+        }
+    }
+
+    #[test]
+    fn pixels_outside_the_stencil_are_skipped_not_panicked() {
+        let mut stencil = Stencil::new_with_dimensions(4, 4);
+
+        stencil.line((-5, -5), (10, 10), 0xFF);
+
+        assert!(get_pixel(&stencil, 0, 0));
+        assert!(get_pixel(&stencil, 3, 3));
+    }
+
+    #[test]
+    fn filled_ellipse_is_symmetric_about_its_center() {
+        let mut stencil = Stencil::new_with_dimensions(21, 21);
+
+        stencil.filled_ellipse((10, 10), 8, 6, &[0xFF; 8]);
+
+        for dx in 0..8 {
+            for dy in 0..6 {
+                assert_eq!(
+                    get_pixel(&stencil, 10 + dx, 10 + dy),
+                    get_pixel(&stencil, 10 - dx, 10 - dy),
+                    "not symmetric at offset ({dx}, {dy})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn filled_ellipse_covers_its_horizontal_and_vertical_extent() {
+        let mut stencil = Stencil::new_with_dimensions(21, 21);
+
+        stencil.filled_ellipse((10, 10), 8, 6, &[0xFF; 8]);
+
+        assert!(get_pixel(&stencil, 10, 10)); // center
+        assert!(get_pixel(&stencil, 2, 10)); // left extent (cx - rx)
+        assert!(get_pixel(&stencil, 18, 10)); // right extent (cx + rx)
+        assert!(get_pixel(&stencil, 10, 4)); // top extent (cy - ry)
+        assert!(get_pixel(&stencil, 10, 16)); // bottom extent (cy + ry)
+        assert!(!get_pixel(&stencil, 0, 10)); // well outside the ellipse
+    }
+
+    #[test]
+    fn zero_radius_ellipse_is_a_noop() {
+        let mut stencil = Stencil::new_with_dimensions(10, 10);
+
+        stencil.filled_ellipse((5, 5), 0, 5, &[0xFF; 8]);
+
+        assert_eq!(stencil.bits, vec![0x00; stencil.get_span() * 10]);
+    }
+}
+
+/// Paint the pair of horizontal spans `ry - y` and `ry + y` pixels from the top of the ellipse's
+/// bounding box, `x` pixels to either side of `cx`. Skips the redundant second span when `y` is
+/// zero, since both spans land on the same row at the equator.
+fn draw_scanline_pair(stencil: &mut Stencil, cx: Unit, cy: Unit, ry: Unit, x: Unit, y: Unit, pattern: &Pattern) {
+    let left = cx - x;
+    let right = cx + x + 1;
+
+    stencil.horizontal_line((left, cy - y), right, pattern[((ry - y) & 7) as usize]);
+    if y != 0 {
+        stencil.horizontal_line((left, cy + y), right, pattern[((ry + y) & 7) as usize]);
+    }
+}